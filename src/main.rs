@@ -1,13 +1,34 @@
-use anyhow::Result;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result, bail};
 use clap::Parser;
-use swim_rs::protocol::node::Node;
+use ed25519_dalek::VerifyingKey;
+use rand::RngCore;
+use swim_rs::protocol::crypto::{Identity, public_key_from_private_key};
+use swim_rs::protocol::pipeline::Pipeline;
 
 
 // TODO: Include verification of the string to socket type
 #[derive(Parser)]
 struct Cli {
     socket: String,
-    seeds: Option<Vec<String>>
+    seeds: Option<Vec<String>>,
+    /// Shared cluster secret used to authenticate datagrams.
+    #[arg(long, default_value = "swim-rs")]
+    secret: String,
+    /// Number of protocol workers in the packet pipeline.
+    #[arg(long, default_value_t = 4)]
+    workers: usize,
+    /// Hex-encoded 32-byte Ed25519 private key identifying this node. A fresh
+    /// key is generated when omitted.
+    #[arg(long)]
+    key: Option<String>,
+    /// Pin a peer's Ed25519 public identity as `addr=hex`. Repeatable. A
+    /// pinned peer's datagrams must carry a valid signature under this key;
+    /// an unpinned peer is still trusted via `--secret` alone.
+    #[arg(long = "peer-key")]
+    peer_keys: Vec<String>,
 }
 
 fn main() -> Result<()> {
@@ -15,16 +36,66 @@ fn main() -> Result<()> {
 
     let args = Cli::parse();
 
-    let mut node = Node::new(args.socket)?;
+    let bind_addr: SocketAddr = args.socket.parse().context("invalid socket address")?;
+    let seeds = args
+        .seeds
+        .unwrap_or_default()
+        .iter()
+        .map(|peer| peer.parse().context("invalid seed address"))
+        .collect::<Result<Vec<SocketAddr>>>()?;
+
+    let private_key = match &args.key {
+        Some(hex) => parse_key(hex)?,
+        None => {
+            let mut key = [0u8; 32];
+            rand::rng().fill_bytes(&mut key);
+            key
+        }
+    };
 
-    if let Some(peers) = args.seeds {
-        for peer in peers {
-            node.join(peer)?;
+    let mut pinned_peers = HashMap::new();
+    for entry in &args.peer_keys {
+        let (addr, key) = parse_peer_key(entry)?;
+        if pinned_peers.insert(addr, key).is_some() {
+            tracing::warn!("--peer-key for {} given more than once; using the last one", addr);
         }
     }
 
-    node.event_loop()?;
+    // Operators can pin this value on peers to verify our identity.
+    let public = public_key_from_private_key(&private_key);
+    tracing::info!("Public identity: {}", hex::encode(public.as_bytes()));
+
+    let identity = Identity::new(private_key, pinned_peers);
+
+    Pipeline::run(bind_addr, args.secret.into_bytes(), identity, seeds, args.workers)?;
 
     Ok(())
 }
 
+/// Decode a hex-encoded 32-byte key of any kind.
+fn parse_hex32(hex: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(hex).context("key must be valid hex")?;
+    if bytes.len() != 32 {
+        bail!("key must be exactly 32 bytes, got {}", bytes.len());
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
+
+/// Decode a hex-encoded 32-byte Ed25519 private key.
+fn parse_key(hex: &str) -> Result<[u8; 32]> {
+    parse_hex32(hex).context("private key must be a 32-byte hex string")
+}
+
+/// Parse a `--peer-key addr=hex` entry into a pinned peer's address and
+/// Ed25519 public key.
+fn parse_peer_key(entry: &str) -> Result<(SocketAddr, VerifyingKey)> {
+    let (addr, hex_key) = entry
+        .split_once('=')
+        .context("--peer-key must be of the form addr=hex")?;
+    let addr: SocketAddr = addr.parse().context("invalid peer address in --peer-key")?;
+    let bytes = parse_hex32(hex_key).context("peer public key must be a 32-byte hex string")?;
+    let key = VerifyingKey::from_bytes(&bytes).context("invalid peer public key")?;
+    Ok((addr, key))
+}
@@ -0,0 +1,455 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length in bytes of the authentication tag appended to every datagram.
+const TAG_LEN: usize = 32;
+/// Length of the big-endian anti-replay counter prefixed to every datagram.
+const COUNTER_LEN: usize = 8;
+/// Length of the big-endian boot id prefixed to every datagram, ahead of the
+/// counter.
+const BOOT_ID_LEN: usize = 8;
+/// Length of the Ed25519 signature appended to every datagram, after the tag.
+const SIGNATURE_LEN: usize = 64;
+/// Length of a symmetric session key.
+pub const SESSION_KEY_LEN: usize = 32;
+
+/// This node's Ed25519 identity, plus any peer public keys the operator has
+/// chosen to pin.
+///
+/// A pinned peer's datagrams must carry a valid signature under its pinned
+/// key, so that peer's identity cannot be spoofed by another node that only
+/// holds the cluster secret. An unpinned peer is still trusted via the
+/// cluster secret, exactly as if pinning did not exist.
+#[derive(Clone)]
+pub struct Identity {
+    signing_key: SigningKey,
+    pinned_peers: HashMap<SocketAddr, VerifyingKey>,
+}
+
+impl Identity {
+    /// Build an identity from a private key seed and the set of peer public
+    /// keys to pin. Pass an empty map to opt out of pinning entirely.
+    pub fn new(private_key: [u8; 32], pinned_peers: HashMap<SocketAddr, VerifyingKey>) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(&private_key),
+            pinned_peers,
+        }
+    }
+
+    /// This node's public identity, suitable for pinning by peers.
+    pub fn public_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+}
+
+/// Derive the Ed25519 public key corresponding to a private seed, so
+/// operators can compute and pin peer identities without the private half.
+pub fn public_key_from_private_key(private_key: &[u8; 32]) -> VerifyingKey {
+    SigningKey::from_bytes(private_key).verifying_key()
+}
+
+/// Per-peer symmetric session key with a rolling epoch.
+///
+/// The previous key is retained across a single rotation so that datagrams in
+/// flight when a rekey lands are still accepted rather than dropped.
+struct Session {
+    epoch: u64,
+    current: [u8; SESSION_KEY_LEN],
+    previous: Option<[u8; SESSION_KEY_LEN]>,
+}
+
+/// Bound on how many retired boot ids we remember per peer, so the memory a
+/// long-lived peer occupies stays flat rather than growing once per restart.
+const RETIRED_BOOT_ID_HISTORY: usize = 8;
+
+/// Replay-tracking state for a single peer: the current boot id's high-water
+/// counter, plus a bounded history of boot ids this peer has since moved past.
+///
+/// A restart is accepted once — the first datagram under a boot id we've
+/// never seen retires the old one — but a captured datagram from that retired
+/// boot id must never be accepted again, even though its counter no longer
+/// has anything live to compare against.
+struct ReplayState {
+    boot_id: u64,
+    counter: u64,
+    retired_boot_ids: VecDeque<u64>,
+}
+
+/// Authenticates datagrams with a shared cluster secret and, once established,
+/// per-peer session keys, and signs every outgoing datagram with this node's
+/// Ed25519 identity.
+///
+/// The baseline trust model is shared-secret only: every node that holds the
+/// cluster secret is mutually trusted, and session keys bootstrap off the
+/// cluster secret to limit the lifetime of any single key. On top of that, an
+/// operator can pin a specific peer's Ed25519 public key (see `Identity`); a
+/// pinned peer's datagrams must then also carry a valid signature under that
+/// key, so a pinned peer's identity cannot be spoofed by another node that
+/// merely holds the cluster secret. A peer nobody has pinned is trusted via
+/// the cluster secret alone, same as before pinning existed. Each datagram
+/// also carries an authenticated, monotonic counter scoped to a random
+/// per-process boot id, so captured packets cannot be replayed within a
+/// process's lifetime, and a bounded history of retired boot ids keeps a
+/// restarted process from being locked out without reopening the replay
+/// window from before the restart.
+pub struct Authenticator {
+    /// Shared cluster secret, used for bootstrap and rekey traffic.
+    cluster_key: [u8; 32],
+    /// This node's Ed25519 identity and any peer keys it pins.
+    identity: Identity,
+    /// Negotiated per-peer session keys, keyed by peer address.
+    sessions: HashMap<SocketAddr, Session>,
+    /// Random identifier generated once per process, stamped into every
+    /// datagram we send alongside `send_counter`. Since a fresh process always
+    /// restarts `send_counter` at 0, peers use a change in boot id to tell a
+    /// restart apart from a replay of stale traffic.
+    boot_id: u64,
+    /// Monotonic counter stamped into every datagram we send.
+    send_counter: u64,
+    /// Per-peer replay state: the current boot id's high-water counter plus a
+    /// bounded history of boot ids this peer has since moved past, so a
+    /// captured datagram from a retired boot id is rejected outright rather
+    /// than mistaken for a fresh restart.
+    seen: HashMap<SocketAddr, ReplayState>,
+}
+
+impl Authenticator {
+    /// Build an authenticator from a cluster secret and this node's identity.
+    pub fn new(cluster_secret: &[u8], identity: Identity) -> Self {
+        Self {
+            cluster_key: derive_key(cluster_secret),
+            identity,
+            sessions: HashMap::new(),
+            boot_id: rand::rng().next_u64(),
+            send_counter: 0,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// This node's public identity, suitable for pinning by peers.
+    pub fn public_key(&self) -> VerifyingKey {
+        self.identity.public_key()
+    }
+
+    /// Authenticate `payload` for `peer`, returning the datagram to send. Uses
+    /// the peer's session key when one exists, otherwise the cluster secret.
+    pub fn seal(&mut self, peer: SocketAddr, payload: &[u8]) -> Vec<u8> {
+        let key = self
+            .sessions
+            .get(&peer)
+            .map(|s| s.current)
+            .unwrap_or(self.cluster_key);
+        self.stamp(&key, payload)
+    }
+
+    /// Authenticate `payload` under the cluster secret regardless of any session
+    /// key. Used for rekey messages, whose new key the peer cannot yet hold.
+    pub fn seal_cluster(&mut self, payload: &[u8]) -> Vec<u8> {
+        let key = self.cluster_key;
+        self.stamp(&key, payload)
+    }
+
+    /// Verify a datagram claimed to come from `peer` and strip its boot id,
+    /// counter, tag, and signature. Returns `None` when no known key
+    /// authenticates it, when it replays a counter already accepted under
+    /// this peer's current boot id, when its boot id is one this peer has
+    /// since moved past, or when `peer` is pinned and the signature does not
+    /// verify under its pinned key. The current and previous session keys are
+    /// tried before falling back to the cluster secret.
+    pub fn open(&mut self, peer: SocketAddr, datagram: &[u8]) -> Option<Vec<u8>> {
+        if datagram.len() < BOOT_ID_LEN + COUNTER_LEN + TAG_LEN + SIGNATURE_LEN {
+            return None;
+        }
+        let (signed, sig_bytes) = datagram.split_at(datagram.len() - SIGNATURE_LEN);
+        let (head, tag) = signed.split_at(signed.len() - TAG_LEN);
+        let (meta, payload) = head.split_at(BOOT_ID_LEN + COUNTER_LEN);
+        let (boot_id_bytes, counter_bytes) = meta.split_at(BOOT_ID_LEN);
+        let boot_id = u64::from_be_bytes(boot_id_bytes.try_into().ok()?);
+        let counter = u64::from_be_bytes(counter_bytes.try_into().ok()?);
+
+        if let Some(state) = self.seen.get(&peer) {
+            if boot_id == state.boot_id {
+                // Reject anything we have already accepted under this boot id.
+                if counter <= state.counter {
+                    return None;
+                }
+            } else if state.retired_boot_ids.contains(&boot_id) {
+                // A captured datagram from a session this peer has already
+                // moved past — do not let it pass for a fresh restart.
+                return None;
+            }
+        }
+
+        let mut candidates: Vec<&[u8; 32]> = Vec::new();
+        if let Some(session) = self.sessions.get(&peer) {
+            candidates.push(&session.current);
+            if let Some(prev) = session.previous.as_ref() {
+                candidates.push(prev);
+            }
+        }
+        candidates.push(&self.cluster_key);
+
+        let authentic = candidates.into_iter().any(|key| verify(key, head, tag));
+        if !authentic {
+            return None;
+        }
+
+        if let Some(pinned) = self.identity.pinned_peers.get(&peer) {
+            let signature = Signature::from_bytes(sig_bytes.try_into().ok()?);
+            if pinned.verify(signed, &signature).is_err() {
+                return None;
+            }
+        }
+
+        match self.seen.get_mut(&peer) {
+            Some(state) if state.boot_id == boot_id => {
+                state.counter = counter;
+            }
+            Some(state) => {
+                // The peer restarted under a boot id we've never retired
+                // before: accept it, and retire the old one so a captured
+                // replay of it can never be mistaken for a restart again.
+                if state.retired_boot_ids.len() >= RETIRED_BOOT_ID_HISTORY {
+                    state.retired_boot_ids.pop_front();
+                }
+                state.retired_boot_ids.push_back(state.boot_id);
+                state.boot_id = boot_id;
+                state.counter = counter;
+            }
+            None => {
+                self.seen.insert(
+                    peer,
+                    ReplayState {
+                        boot_id,
+                        counter,
+                        retired_boot_ids: VecDeque::new(),
+                    },
+                );
+            }
+        }
+
+        Some(payload.to_vec())
+    }
+
+    /// Generate a fresh session key for `peer`, retiring the current key to the
+    /// grace slot, and return the new key to advertise via a rekey message.
+    pub fn rotate(&mut self, peer: SocketAddr) -> (u64, [u8; SESSION_KEY_LEN]) {
+        let mut key = [0u8; SESSION_KEY_LEN];
+        rand::rng().fill_bytes(&mut key);
+
+        let cluster_key = self.cluster_key;
+        let session = self.sessions.entry(peer).or_insert_with(|| Session {
+            epoch: 0,
+            current: cluster_key,
+            previous: None,
+        });
+        session.previous = Some(session.current);
+        session.current = key;
+        session.epoch += 1;
+
+        (session.epoch, key)
+    }
+
+    /// Install a session key advertised by `peer`. Older epochs are ignored so
+    /// out-of-order rekey messages cannot downgrade the link.
+    pub fn install_session(&mut self, peer: SocketAddr, epoch: u64, key: [u8; SESSION_KEY_LEN]) {
+        let cluster_key = self.cluster_key;
+        let session = self.sessions.entry(peer).or_insert_with(|| Session {
+            epoch: 0,
+            current: cluster_key,
+            previous: None,
+        });
+        if epoch < session.epoch {
+            return;
+        }
+        session.previous = Some(session.current);
+        session.current = key;
+        session.epoch = epoch;
+    }
+
+    /// Prefix the boot id and a fresh counter, authenticate
+    /// `boot_id || counter || payload`, append the tag, then sign the whole
+    /// thing with this node's Ed25519 identity so a peer that pins our public
+    /// key can verify it came from us specifically.
+    fn stamp(&mut self, key: &[u8], payload: &[u8]) -> Vec<u8> {
+        self.send_counter = self.send_counter.wrapping_add(1);
+        let counter = self.send_counter;
+
+        let mut head = Vec::with_capacity(BOOT_ID_LEN + COUNTER_LEN + payload.len());
+        head.extend_from_slice(&self.boot_id.to_be_bytes());
+        head.extend_from_slice(&counter.to_be_bytes());
+        head.extend_from_slice(payload);
+
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(&head);
+        let tag = mac.finalize().into_bytes();
+        head.extend_from_slice(&tag);
+
+        let signature = self.identity.signing_key.sign(&head);
+        head.extend_from_slice(&signature.to_bytes());
+        head
+    }
+}
+
+/// Verify that `tag` authenticates `head` under `key`.
+fn verify(key: &[u8], head: &[u8], tag: &[u8]) -> bool {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(head);
+    mac.verify_slice(tag).is_ok()
+}
+
+/// Hash an arbitrary secret into a fixed-size symmetric key.
+fn derive_key(secret: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(secret);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer() -> SocketAddr {
+        "127.0.0.1:7000".parse().unwrap()
+    }
+
+    /// An authenticator with a fixed identity and no pinned peers, for tests
+    /// that only care about the cluster-secret path.
+    fn auth(cluster_secret: &[u8]) -> Authenticator {
+        Authenticator::new(cluster_secret, Identity::new([0u8; 32], HashMap::new()))
+    }
+
+    #[test]
+    fn seal_open_round_trip() {
+        let mut sender = auth(b"cluster");
+        let mut receiver = auth(b"cluster");
+        let datagram = sender.seal(peer(), b"hello");
+        assert_eq!(receiver.open(peer(), &datagram).as_deref(), Some(&b"hello"[..]));
+    }
+
+    #[test]
+    fn wrong_secret_is_rejected() {
+        let mut sender = auth(b"cluster");
+        let mut receiver = auth(b"other");
+        let datagram = sender.seal(peer(), b"hello");
+        assert!(receiver.open(peer(), &datagram).is_none());
+    }
+
+    #[test]
+    fn replayed_datagram_is_rejected() {
+        let mut sender = auth(b"cluster");
+        let mut receiver = auth(b"cluster");
+        let datagram = sender.seal(peer(), b"ping");
+        assert!(receiver.open(peer(), &datagram).is_some());
+        // Replaying the same datagram does not advance the counter.
+        assert!(receiver.open(peer(), &datagram).is_none());
+    }
+
+    #[test]
+    fn rotation_keeps_previous_key_in_grace_window() {
+        let mut sender = auth(b"cluster");
+        let mut receiver = auth(b"cluster");
+
+        let (epoch, key) = sender.rotate(peer());
+        receiver.install_session(peer(), epoch, key);
+        let in_flight = sender.seal(peer(), b"old");
+
+        // A second rotation retires the first key to the grace slot.
+        let (epoch, key) = sender.rotate(peer());
+        receiver.install_session(peer(), epoch, key);
+
+        // The datagram sealed under the now-previous key still authenticates.
+        assert_eq!(receiver.open(peer(), &in_flight).as_deref(), Some(&b"old"[..]));
+        // So does traffic under the current key.
+        let current = sender.seal(peer(), b"new");
+        assert_eq!(receiver.open(peer(), &current).as_deref(), Some(&b"new"[..]));
+    }
+
+    #[test]
+    fn restarted_peer_is_accepted_not_locked_out() {
+        let mut sender = auth(b"cluster");
+        let mut receiver = auth(b"cluster");
+
+        let first = sender.seal(peer(), b"before restart");
+        assert!(receiver.open(peer(), &first).is_some());
+
+        // The peer process restarts: a fresh `Authenticator` restarts its
+        // counter at 0, which is <= what the receiver last accepted.
+        let mut sender = auth(b"cluster");
+        let after_restart = sender.seal(peer(), b"after restart");
+        assert_eq!(
+            receiver.open(peer(), &after_restart).as_deref(),
+            Some(&b"after restart"[..])
+        );
+    }
+
+    #[test]
+    fn replay_from_a_retired_boot_id_is_rejected_after_restart() {
+        let mut sender = auth(b"cluster");
+        let mut receiver = auth(b"cluster");
+
+        // Sealed under boot A.
+        let captured = sender.seal(peer(), b"captured");
+        assert!(receiver.open(peer(), &captured).is_some());
+
+        // The peer "restarts" under boot B and exchanges several packets.
+        let mut sender = auth(b"cluster");
+        for _ in 0..5 {
+            let datagram = sender.seal(peer(), b"after restart");
+            assert!(receiver.open(peer(), &datagram).is_some());
+        }
+
+        // A replay of the original boot-A datagram must not be mistaken for
+        // another restart just because its boot id no longer matches current.
+        assert!(receiver.open(peer(), &captured).is_none());
+    }
+
+    #[test]
+    fn stale_epoch_does_not_downgrade() {
+        let mut a = auth(b"cluster");
+        a.install_session(peer(), 5, [1u8; SESSION_KEY_LEN]);
+        a.install_session(peer(), 2, [2u8; SESSION_KEY_LEN]);
+        assert_eq!(a.sessions[&peer()].epoch, 5);
+        assert_eq!(a.sessions[&peer()].current, [1u8; SESSION_KEY_LEN]);
+    }
+
+    #[test]
+    fn pinned_peer_signature_must_match_its_pinned_key() {
+        let sender_key = [3u8; 32];
+        let impostor_key = [4u8; 32];
+        let sender_identity = Identity::new(sender_key, HashMap::new());
+        let sender_public = sender_identity.public_key();
+        let mut sender = Authenticator::new(b"cluster", sender_identity);
+
+        let mut pinned = HashMap::new();
+        pinned.insert(peer(), sender_public);
+        let mut receiver = Authenticator::new(b"cluster", Identity::new([0u8; 32], pinned));
+
+        // The genuine sender's signature verifies against the pinned key.
+        let datagram = sender.seal(peer(), b"hello");
+        assert_eq!(receiver.open(peer(), &datagram).as_deref(), Some(&b"hello"[..]));
+
+        // An impostor who only knows the cluster secret, but not the pinned
+        // peer's private key, is rejected even though its HMAC tag is valid.
+        let mut impostor = Authenticator::new(b"cluster", Identity::new(impostor_key, HashMap::new()));
+        let forged = impostor.seal(peer(), b"forged");
+        assert!(receiver.open(peer(), &forged).is_none());
+    }
+
+    #[test]
+    fn unpinned_peer_is_trusted_via_cluster_secret_alone() {
+        // No pinning configured for this peer: any identity key authenticates
+        // as long as the HMAC tag checks out, same as before pinning existed.
+        let mut sender = Authenticator::new(b"cluster", Identity::new([9u8; 32], HashMap::new()));
+        let mut receiver = auth(b"cluster");
+        let datagram = sender.seal(peer(), b"hello");
+        assert_eq!(receiver.open(peer(), &datagram).as_deref(), Some(&b"hello"[..]));
+    }
+}
@@ -2,17 +2,54 @@ use std::net::SocketAddr;
 
 use serde::{Deserialize, Serialize};
 
+use crate::protocol::node::PeerState;
+
+/// A single piggybacked membership change gossiped between nodes.
+///
+/// Updates ride along on ordinary probe traffic (see `Node::queue_send`) so
+/// that a state transition observed by one node eventually reaches the whole
+/// cluster without any dedicated broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipUpdate {
+    pub addr: SocketAddr,
+    pub incarnation: u32,
+    pub state: PeerState,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Message {
     /// Direct probe - expects Ack back
-    Ping { seq: u32, from: SocketAddr },
+    Ping {
+        seq: u32,
+        from: SocketAddr,
+        updates: Vec<MembershipUpdate>,
+    },
     /// Response to Ping or PingReq
-    Ack { seq: u32, from: SocketAddr },
+    Ack {
+        seq: u32,
+        from: SocketAddr,
+        updates: Vec<MembershipUpdate>,
+    },
+    /// Ack forwarded by an intermediary back to the original indirect-probe
+    /// requester, confirming `target` for the requester's `seq`.
+    AckRelay {
+        seq: u32,
+        from: SocketAddr,
+        target: SocketAddr,
+        updates: Vec<MembershipUpdate>,
+    },
     /// Indirect probe request - asks a node to ping target on our behalf
     PingReq {
         seq: u32,
         from: SocketAddr,
         target: SocketAddr,
+        updates: Vec<MembershipUpdate>,
+    },
+    /// Advertise a fresh per-peer session key during key rotation
+    Rekey {
+        from: SocketAddr,
+        epoch: u64,
+        key: [u8; 32],
     },
 }
 
@@ -24,4 +61,51 @@ impl Message {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
         postcard::from_bytes(bytes)
     }
+
+    /// The address this message claims to originate from. Advisory only: the
+    /// receiver trusts the datagram's UDP source address and uses this solely to
+    /// detect and reject a mismatch (see `Node::handle_message`).
+    pub fn claimed_from(&self) -> SocketAddr {
+        match self {
+            Message::Ping { from, .. }
+            | Message::Ack { from, .. }
+            | Message::AckRelay { from, .. }
+            | Message::PingReq { from, .. }
+            | Message::Rekey { from, .. } => *from,
+        }
+    }
+
+    /// Short human-readable label for log lines.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Message::Ping { .. } => "PING",
+            Message::Ack { .. } => "ACK",
+            Message::AckRelay { .. } => "ACK-RELAY",
+            Message::PingReq { .. } => "PING-REQ",
+            Message::Rekey { .. } => "REKEY",
+        }
+    }
+
+    /// Membership updates piggybacked on this message.
+    pub fn updates(&self) -> &[MembershipUpdate] {
+        match self {
+            Message::Ping { updates, .. }
+            | Message::Ack { updates, .. }
+            | Message::AckRelay { updates, .. }
+            | Message::PingReq { updates, .. } => updates,
+            Message::Rekey { .. } => &[],
+        }
+    }
+
+    /// Attach gossip updates to this message before it goes on the wire.
+    /// Control messages such as `Rekey` carry no gossip and are left untouched.
+    pub fn set_updates(&mut self, gossip: Vec<MembershipUpdate>) {
+        match self {
+            Message::Ping { updates, .. }
+            | Message::Ack { updates, .. }
+            | Message::AckRelay { updates, .. }
+            | Message::PingReq { updates, .. } => *updates = gossip,
+            Message::Rekey { .. } => {}
+        }
+    }
 }
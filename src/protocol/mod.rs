@@ -0,0 +1,5 @@
+pub mod crypto;
+pub mod messages;
+pub mod metrics;
+pub mod node;
+pub mod pipeline;
@@ -1,24 +1,69 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    io,
-    net::{SocketAddr, ToSocketAddrs},
+    collections::{HashMap, VecDeque, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
     time::{Duration, Instant},
 };
 
 use anyhow::Result;
-use mio::{Events, Interest, Poll, Token, net::UdpSocket};
 use rand::RngCore;
 use tracing::{info, warn};
 
-use crate::protocol::messages::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::crypto::{Authenticator, Identity};
+use crate::protocol::messages::{MembershipUpdate, Message};
 use crate::protocol::metrics::LatencyMetrics;
 
 // Protocol timing constants
-const TICK_INTERVAL: Duration = Duration::from_secs(1);
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
 const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
 const SUSPECT_TIMEOUT: Duration = Duration::from_secs(3);
 const INDIRECT_PROBE_COUNT: usize = 3;
 
+// Gossip dissemination constants
+/// Maximum number of updates piggybacked on a single outgoing message.
+const MAX_PIGGYBACK: usize = 6;
+/// Retransmit multiplier: each update is sent `ceil(LAMBDA * log2(N+1))` times.
+const LAMBDA: f64 = 3.0;
+
+// Phi-accrual failure detection constants
+/// Per-peer samples retained for the RTT and inter-arrival windows.
+const RTT_HISTORY: usize = 64;
+/// Default phi threshold above which a peer is suspected.
+const PHI_THRESHOLD: f64 = 8.0;
+/// Samples required before a window trusts its own statistics.
+const PHI_MIN_SAMPLES: usize = 8;
+/// Floor on the inter-arrival standard deviation (seconds) for phi.
+const PHI_STDDEV_FLOOR: f64 = 0.200;
+/// Assumed mean inter-arrival interval (seconds) before enough samples exist.
+const PHI_DEFAULT_MEAN: f64 = 1.000;
+/// Floor on the RTT standard deviation (seconds) for the adaptive timeout.
+const RTT_STDDEV_FLOOR: f64 = 0.010;
+/// Assumed mean RTT (seconds) before enough samples are collected.
+const RTT_DEFAULT_MEAN: f64 = 0.100;
+/// Probe-timeout safety factor: effective timeout = μ + k·σ.
+const PROBE_TIMEOUT_K: f64 = 4.0;
+
+/// How many ticks (≈ seconds) between per-peer session key rotations.
+const REKEY_INTERVAL: u64 = 60;
+
+/// How long an unfulfilled indirect probe request is tracked before expiry.
+const INDIRECT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tracks an indirect probe we are performing on another node's behalf, so the
+/// resulting Ack can be relayed back to the original requester.
+pub struct IndirectRequest {
+    pub seq: u32,
+    pub target: SocketAddr,
+    pub requester: SocketAddr,
+    pub created_at: Instant,
+}
+
 /// Tracks an outgoing probe that's awaiting an Ack
 pub struct PendingProbe {
     pub seq: u32,
@@ -28,75 +73,263 @@ pub struct PendingProbe {
     pub indirect_sent: bool,
 }
 
-/// Queued outgoing message
-struct OutgoingMessage {
-    data: Vec<u8>,
-    target: SocketAddr,
+/// A sealed, ready-to-send datagram with its destination.
+///
+/// Protocol workers produce these; socket workers drain them to the wire (see
+/// the `pipeline` module).
+pub struct OutboundPacket {
+    pub data: Vec<u8>,
+    pub target: SocketAddr,
+}
+
+/// A membership update pending dissemination, with its retransmit counter.
+///
+/// Each update is gossiped a bounded number of times (see `LAMBDA`) before it
+/// is evicted from the buffer, giving it high odds of reaching every member
+/// while keeping per-packet overhead constant.
+struct PendingUpdate {
+    update: MembershipUpdate,
+    retransmits: u32,
+}
+
+/// Assign a peer address to one of `workers` protocol-worker shards. Every
+/// layer — the pipeline's inbound dispatch and each `Node`'s membership
+/// filtering — routes through this single function so a peer's membership
+/// state is only ever held by one worker.
+pub fn shard_for(addr: SocketAddr, workers: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    addr.hash(&mut hasher);
+    (hasher.finish() % workers as u64) as usize
 }
 
 pub struct Membership {
-    pub self_seq: u32,
+    /// Our own incarnation/sequence counter. Shared across all protocol
+    /// workers via an atomic so every shard advertises and refutes under one
+    /// monotonic incarnation for this node.
+    self_seq: Arc<AtomicU32>,
     pub members: HashMap<SocketAddr, Member>,
 }
 
 impl Membership {
-    pub fn new() -> Self {
+    pub fn new(self_seq: Arc<AtomicU32>) -> Self {
         Self {
-            self_seq: 0,
+            self_seq,
             members: HashMap::new(),
         }
     }
 
     pub fn next_seq(&mut self) -> u32 {
-        let seq = self.self_seq;
-        self.self_seq = self.self_seq.wrapping_add(1);
-        seq
+        self.self_seq.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Advance the shared self incarnation strictly past `incarnation`,
+    /// returning the new value. Uses a compare-and-swap loop so concurrent
+    /// refutations on other workers never lose an increment.
+    fn refute_past(&self, incarnation: u32) -> u32 {
+        let mut current = self.self_seq.load(Ordering::Relaxed);
+        loop {
+            let next = current.max(incarnation).wrapping_add(1);
+            match self.self_seq.compare_exchange_weak(
+                current,
+                next,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return next,
+                Err(observed) => current = observed,
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PeerState {
     Active,
     Suspect,
     Dead,
 }
 
+impl PeerState {
+    /// Tie-break rank used when two updates share an incarnation number:
+    /// `Active < Suspect < Dead`.
+    fn precedence(self) -> u8 {
+        match self {
+            PeerState::Active => 0,
+            PeerState::Suspect => 1,
+            PeerState::Dead => 2,
+        }
+    }
+}
+
 pub struct Member {
     pub state: PeerState,
     pub incarnation: u32,
     pub last_state_change: Instant,
+    /// Recent observed RTTs, driving the adaptive probe timeout.
+    pub rtts: VecDeque<Duration>,
+    /// Recent intervals between successive messages from this peer, driving the
+    /// phi-accrual estimator.
+    pub intervals: VecDeque<Duration>,
+    /// When we last heard from this peer (refreshed on every message we
+    /// receive from it, including gossip-bearing traffic).
+    pub last_heard: Instant,
+}
+
+impl Member {
+    fn new(state: PeerState) -> Self {
+        Self {
+            state,
+            incarnation: 0,
+            last_state_change: Instant::now(),
+            rtts: VecDeque::with_capacity(RTT_HISTORY),
+            intervals: VecDeque::with_capacity(RTT_HISTORY),
+            last_heard: Instant::now(),
+        }
+    }
+
+    /// Record an observed RTT sample used by the adaptive probe timeout.
+    fn record_rtt(&mut self, rtt: Duration) {
+        if self.rtts.len() >= RTT_HISTORY {
+            self.rtts.pop_front();
+        }
+        self.rtts.push_back(rtt);
+    }
+
+    /// Note that we just heard from this peer: record the inter-arrival
+    /// interval since the previous message and refresh `last_heard`.
+    fn record_heard(&mut self) {
+        let gap = self.last_heard.elapsed();
+        if self.intervals.len() >= RTT_HISTORY {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(gap);
+        self.last_heard = Instant::now();
+    }
+
+    /// Mean and standard deviation (in seconds) of `samples`, falling back to
+    /// `(default_mean, 0.0)` until `PHI_MIN_SAMPLES` have accumulated.
+    fn stats(samples: &VecDeque<Duration>, default_mean: f64) -> (f64, f64) {
+        if samples.len() < PHI_MIN_SAMPLES {
+            return (default_mean, 0.0);
+        }
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|d| d.as_secs_f64()).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|d| {
+                let diff = d.as_secs_f64() - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / n;
+        (mean, variance.sqrt())
+    }
+
+    /// Suspicion level for this peer given the current time, as the phi-accrual
+    /// value `phi = -log10(P(t_now - last_heard))` evaluated against the
+    /// inter-arrival distribution.
+    fn phi(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.last_heard).as_secs_f64();
+        let (mean, stddev) = Self::stats(&self.intervals, PHI_DEFAULT_MEAN);
+        let stddev = stddev.max(PHI_STDDEV_FLOOR);
+        let tail = normal_tail(elapsed, mean, stddev).max(f64::MIN_POSITIVE);
+        -tail.log10()
+    }
+
+    /// Adaptive probe timeout for this peer: `μ + k·σ` over the RTT window,
+    /// never below the floor.
+    fn effective_timeout(&self) -> Duration {
+        let (mean, stddev) = Self::stats(&self.rtts, RTT_DEFAULT_MEAN);
+        let secs = mean + PROBE_TIMEOUT_K * stddev.max(RTT_STDDEV_FLOOR);
+        Duration::from_secs_f64(secs).max(PROBE_TIMEOUT)
+    }
+}
+
+/// Upper-tail probability `P(X >= x)` for `X ~ Normal(mean, stddev)`, using an
+/// Abramowitz–Stegun approximation of the error function.
+fn normal_tail(x: f64, mean: f64, stddev: f64) -> f64 {
+    let z = (x - mean) / (stddev * std::f64::consts::SQRT_2);
+    0.5 * erfc(z)
+}
+
+/// Complementary error function via Abramowitz & Stegun 7.1.26.
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    let erf = if x < 0.0 { -erf } else { erf };
+    1.0 - erf
 }
 
 pub struct Node {
-    pub socket: UdpSocket,
     pub local_addr: SocketAddr,
+    /// This worker's shard index and the total worker count. The node only
+    /// ever holds and probes members that hash to `shard`; piggybacked gossip
+    /// about a peer in another shard is queued in `forward_queue` rather than
+    /// merged here, since a gossip item's subject is unrelated to whichever
+    /// worker happened to receive the packet carrying it.
+    shard: usize,
+    workers: usize,
     pub members: Membership,
     pub probes: Vec<PendingProbe>,
-    send_queue: VecDeque<OutgoingMessage>,
+    /// Indirect probes in flight on behalf of other nodes. The requester and
+    /// the target of a single indirect probe generally hash to different
+    /// shards, so this is shared across every worker (like `self_seq`)
+    /// rather than owned per-`Node` — otherwise the shard that services the
+    /// target's `Ack` could never find the entry the requester's shard
+    /// created.
+    pub indirect_requests: Arc<Mutex<Vec<IndirectRequest>>>,
+    send_queue: VecDeque<OutboundPacket>,
+    /// Piggybacked updates received whose subject belongs to another worker's
+    /// shard, awaiting pickup by the pipeline (see `drain_forwards`).
+    forward_queue: VecDeque<MembershipUpdate>,
+    /// Membership updates awaiting gossip dissemination.
+    dissemination: Vec<PendingUpdate>,
+    /// Phi-accrual suspicion threshold (see `PHI_THRESHOLD`).
+    phi_threshold: f64,
+    /// Datagram authentication and session-key rotation.
+    auth: Authenticator,
+    /// Tick counter driving periodic session-key rotation.
+    rekey_counter: u64,
     last_tick: Instant,
     pub metrics: LatencyMetrics,
 }
 
-// Single token for our UDP socket
-const UDP_SOCKET: Token = Token(0);
-
 impl Node {
-    pub fn new(addr: String) -> Result<Self> {
-        let socket_addr = addr.to_socket_addrs()?.last().unwrap();
-        let socket = UdpSocket::bind(socket_addr)?;
-        let local_addr = socket.local_addr()?;
-
-        info!("Node started on {}", local_addr);
+    /// Build a protocol worker bound to `local_addr`. Socket I/O is owned by the
+    /// `pipeline` layer; a `Node` only maintains protocol state and emits sealed
+    /// datagrams into its outbound queue.
+    pub fn new(
+        local_addr: SocketAddr,
+        cluster_secret: &[u8],
+        identity: Identity,
+        shard: usize,
+        workers: usize,
+        self_seq: Arc<AtomicU32>,
+        indirect_requests: Arc<Mutex<Vec<IndirectRequest>>>,
+    ) -> Self {
+        let auth = Authenticator::new(cluster_secret, identity);
+        info!("Protocol worker {}/{} for {}", shard, workers, local_addr);
 
-        Ok(Self {
-            socket,
+        Self {
             local_addr,
-            members: Membership::new(),
+            shard,
+            workers: workers.max(1),
+            members: Membership::new(self_seq),
             probes: Vec::new(),
+            indirect_requests,
             send_queue: VecDeque::new(),
+            forward_queue: VecDeque::new(),
+            dissemination: Vec::new(),
+            phi_threshold: PHI_THRESHOLD,
+            auth,
+            rekey_counter: 0,
             last_tick: Instant::now(),
             metrics: LatencyMetrics::new(1000), // Keep last 1000 samples
-        })
+        }
     }
 
     /// Queue a join request to the specified peer address.
@@ -123,10 +356,10 @@ impl Node {
         let msg = Message::Ping {
             seq,
             from: self.local_addr,
+            updates: Vec::new(),
         };
 
-        let bytes = msg.to_bytes()?;
-        self.queue_send(bytes, target);
+        self.queue_send(msg, target)?;
         self.metrics.record_ping_sent();
         info!("Sent PING seq={} to {}", seq, target);
 
@@ -164,9 +397,9 @@ impl Node {
                 seq,
                 from: self.local_addr,
                 target,
+                updates: Vec::new(),
             };
-            let bytes = msg.to_bytes()?;
-            self.queue_send(bytes, intermediary);
+            self.queue_send(msg, intermediary)?;
             info!(
                 "Sent PING-REQ seq={} to {} for target {}",
                 seq, intermediary, target
@@ -176,138 +409,423 @@ impl Node {
         Ok(())
     }
 
-    fn queue_send(&mut self, data: Vec<u8>, target: SocketAddr) {
-        self.send_queue.push_back(OutgoingMessage { data, target });
+    /// Attach piggybacked gossip to `msg`, serialize it, and enqueue it.
+    fn queue_send(&mut self, mut msg: Message, target: SocketAddr) -> Result<()> {
+        // Rekey never carries updates (`set_updates` is a no-op for it), so
+        // selecting here would bump and potentially evict pending updates'
+        // retransmit budgets for gossip that never actually goes out.
+        if !matches!(msg, Message::Rekey { .. }) {
+            msg.set_updates(self.select_updates(MAX_PIGGYBACK));
+        }
+        let payload = msg.to_bytes()?;
+        // Rekey traffic must authenticate under the cluster secret, since the
+        // peer cannot yet hold the session key it advertises.
+        let data = match msg {
+            Message::Rekey { .. } => self.auth.seal_cluster(&payload),
+            _ => self.auth.seal(target, &payload),
+        };
+        self.send_queue.push_back(OutboundPacket { data, target });
+        Ok(())
     }
 
-    fn flush_send_queue(&mut self) -> io::Result<bool> {
-        while let Some(msg) = self.send_queue.front() {
-            match self.socket.send_to(&msg.data, msg.target) {
-                Ok(bytes) => {
-                    info!("Sent {} bytes to {}", bytes, msg.target);
-                    self.send_queue.pop_front();
-                }
-                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-                    return Ok(false);
+    /// Authenticate, parse, and handle a raw datagram observed from `source`.
+    /// Packets that fail authentication are dropped before the protocol layer
+    /// sees them.
+    pub fn process_datagram(&mut self, source: SocketAddr, datagram: &[u8]) {
+        let payload = match self.auth.open(source, datagram) {
+            Some(payload) => payload,
+            None => {
+                warn!("Rejected unauthenticated datagram from {}", source);
+                return;
+            }
+        };
+
+        match Message::from_bytes(&payload) {
+            Ok(msg) => {
+                if let Err(e) = self.handle_message(source, msg) {
+                    warn!("Error handling message: {}", e);
                 }
-                Err(e) => return Err(e),
             }
+            Err(e) => warn!("Failed to parse message: {}", e),
         }
-        Ok(true)
     }
 
-    fn handle_message(&mut self, msg: Message) -> Result<()> {
+    /// Drain all datagrams queued for transmission since the last call.
+    pub fn drain_outbound(&mut self) -> Vec<OutboundPacket> {
+        self.send_queue.drain(..).collect()
+    }
+
+    /// Drain piggybacked updates learned about peers outside this worker's
+    /// shard, for the pipeline to forward to the worker that owns each one.
+    pub fn drain_forwards(&mut self) -> Vec<MembershipUpdate> {
+        self.forward_queue.drain(..).collect()
+    }
+
+    /// Apply a membership update forwarded from another shard because this
+    /// worker owns the subject's address (see `Pipeline`'s shard routing).
+    pub fn merge_forwarded(&mut self, update: MembershipUpdate) {
+        self.merge_update(update.addr, update.incarnation, update.state);
+    }
+
+    /// Run a protocol tick if `TICK_INTERVAL` has elapsed since the previous one.
+    pub fn maybe_tick(&mut self) -> Result<()> {
+        if self.last_tick.elapsed() >= TICK_INTERVAL {
+            self.tick()?;
+            self.last_tick = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Select up to `max` pending updates with the lowest retransmit counts,
+    /// bumping each selected counter and evicting any update that has now been
+    /// sent its full `ceil(LAMBDA * log2(N+1))` budget.
+    fn select_updates(&mut self, max: usize) -> Vec<MembershipUpdate> {
+        if self.dissemination.is_empty() {
+            return Vec::new();
+        }
+
+        let budget = self.retransmit_budget();
+        self.dissemination.sort_by_key(|u| u.retransmits);
+
+        let mut selected = Vec::new();
+        for pending in self.dissemination.iter_mut().take(max) {
+            selected.push(pending.update.clone());
+            pending.retransmits += 1;
+        }
+
+        self.dissemination.retain(|u| u.retransmits < budget);
+        selected
+    }
+
+    /// Number of times an update should be retransmitted:
+    /// `ceil(LAMBDA * log2(N+1))`, at least once, where N is the number of
+    /// members this worker's shard tracks rather than the whole cluster.
+    fn retransmit_budget(&self) -> u32 {
+        let n = self.members.members.len();
+        let budget = (LAMBDA * ((n + 1) as f64).log2()).ceil() as u32;
+        budget.max(1)
+    }
+
+    /// Queue a membership change for gossip, superseding any pending update
+    /// about the same peer.
+    fn disseminate(&mut self, update: MembershipUpdate) {
+        self.dissemination.retain(|u| u.update.addr != update.addr);
+        self.dissemination.push(PendingUpdate {
+            update,
+            retransmits: 0,
+        });
+    }
+
+    fn handle_message(&mut self, source: SocketAddr, msg: Message) -> Result<()> {
+        // The UDP source address is authoritative. A well-behaved peer always
+        // stamps its own address in `from`, so a disagreement means the payload
+        // was spoofed or relayed by a misconfigured node: reject it outright
+        // rather than act on any of its contents.
+        let claimed = msg.claimed_from();
+        if source != claimed {
+            warn!(
+                "Rejecting {} from {}: claimed from {} disagrees with source",
+                msg.kind(),
+                source,
+                claimed
+            );
+            return Ok(());
+        }
+
+        // Merge piggybacked membership updates before acting on the probe. A
+        // gossip item's subject is unrelated to the packet's sender, so this
+        // worker only merges updates whose subject it owns; the rest are
+        // queued for the pipeline to forward to the worker that does (see
+        // `drain_forwards`).
+        for update in msg.updates() {
+            if update.addr == self.local_addr || self.owns(update.addr) {
+                self.merge_update(update.addr, update.incarnation, update.state);
+            } else {
+                self.forward_queue.push_back(update.clone());
+            }
+        }
+
+        // Any traffic from a peer — including gossip-bearing probes — refreshes
+        // its liveness and feeds the inter-arrival distribution.
+        self.note_heard(source);
+
         match msg {
-            Message::Ping { seq, from } => {
-                info!("Received PING seq={} from {}", seq, from);
+            Message::Ping { seq, .. } => {
+                info!("Received PING seq={} from {}", seq, source);
 
-                // Ensure sender is in our membership list
-                self.ensure_member(from);
+                // Ensure sender is in our membership list, keyed off the
+                // observed source rather than the self-reported `from`.
+                self.ensure_member(source);
 
-                // Send Ack back
+                // Send Ack back to the address we actually heard from
                 let ack = Message::Ack {
                     seq,
                     from: self.local_addr,
+                    updates: Vec::new(),
                 };
-                let bytes = ack.to_bytes()?;
-                self.queue_send(bytes, from);
+                self.queue_send(ack, source)?;
             }
 
-            Message::Ack { seq, from } => {
-                // Calculate RTT if we have a matching probe
-                if let Some(probe) = self.probes.iter().find(|p| p.seq == seq && p.target == from) {
-                    let rtt = probe.sent_at.elapsed();
+            Message::Ack { seq, .. } => {
+                // Match the probe by the datagram source, so a spoofed `from`
+                // cannot satisfy a probe we sent to a different peer.
+                let rtt = self
+                    .probes
+                    .iter()
+                    .find(|p| p.seq == seq && p.target == source)
+                    .map(|p| p.sent_at.elapsed());
+
+                if let Some(rtt) = rtt {
                     self.metrics.record_rtt(rtt);
-                    info!("Received ACK seq={} from {} (RTT: {:?})", seq, from, rtt);
+                    info!("Received ACK seq={} from {} (RTT: {:?})", seq, source, rtt);
                 } else {
-                    info!("Received ACK seq={} from {} (no matching probe)", seq, from);
+                    info!("Received ACK seq={} from {} (no matching probe)", seq, source);
                 }
 
                 // Remove matching probe
-                self.probes.retain(|p| !(p.seq == seq && p.target == from));
+                self.probes.retain(|p| !(p.seq == seq && p.target == source));
 
                 // Mark member as active
-                self.mark_active(from);
+                self.mark_active(source);
+
+                // Record the RTT sample for the adaptive probe timeout. The
+                // inter-arrival interval and liveness were already refreshed by
+                // `note_heard` at the top of this handler.
+                if let Some(rtt) = rtt {
+                    if let Some(member) = self.members.members.get_mut(&source) {
+                        member.record_rtt(rtt);
+                    }
+                }
+
+                // Relay the ack to anyone who asked us to probe this peer. The
+                // requester's shard may differ from ours, so this is looked up
+                // in the cross-shard shared table rather than a local list.
+                let requesters: Vec<SocketAddr> = {
+                    let mut indirect_requests = self.indirect_requests.lock().unwrap();
+                    let requesters = indirect_requests
+                        .iter()
+                        .filter(|r| r.seq == seq && r.target == source)
+                        .map(|r| r.requester)
+                        .collect();
+                    indirect_requests.retain(|r| !(r.seq == seq && r.target == source));
+                    requesters
+                };
+
+                for requester in requesters {
+                    info!("Relaying ACK seq={} for {} to {}", seq, source, requester);
+                    let relay = Message::AckRelay {
+                        seq,
+                        from: self.local_addr,
+                        target: source,
+                        updates: Vec::new(),
+                    };
+                    self.queue_send(relay, requester)?;
+                }
             }
 
-            Message::PingReq { seq, from, target } => {
+            Message::PingReq { seq, target, .. } => {
                 info!(
                     "Received PING-REQ seq={} from {} for target {}",
-                    seq, from, target
+                    seq, source, target
                 );
 
-                // Ensure requester is in our membership
-                self.ensure_member(from);
+                // Ensure requester is in our membership, keyed off the source
+                self.ensure_member(source);
 
-                // Send a ping to target, but when we get an ack, forward it to `from`
-                // For simplicity, we'll directly ping and let the ack handling work
-                // We need to track that this is on behalf of someone else
+                // Track the request so the target's Ack can be relayed back to
+                // the requester we actually heard from. Shared across shards
+                // since the target's Ack may land on a different worker than
+                // the one handling this PingReq.
+                self.indirect_requests.lock().unwrap().push(IndirectRequest {
+                    seq,
+                    target,
+                    requester: source,
+                    created_at: Instant::now(),
+                });
 
-                // Send ping to target
+                // Send ping to target on the requester's behalf
                 let ping = Message::Ping {
                     seq,
                     from: self.local_addr,
+                    updates: Vec::new(),
                 };
-                let bytes = ping.to_bytes()?;
-                self.queue_send(bytes, target);
+                self.queue_send(ping, target)?;
+            }
+
+            Message::AckRelay { seq, target, .. } => {
+                info!(
+                    "Received ACK-RELAY seq={} for target {} via {}",
+                    seq, target, source
+                );
 
-                // We'll also need to forward any ack we receive back to the original requester
-                // For now, simplified: we just ping the target
-                // A full implementation would track indirect probe requests
+                // An intermediary confirmed `target` for our original probe.
+                let confirmed = self.probes.iter().any(|p| p.seq == seq && p.target == target);
+                if confirmed {
+                    self.probes.retain(|p| !(p.seq == seq && p.target == target));
+                    self.mark_active(target);
+                }
+            }
+
+            Message::Rekey { epoch, key, .. } => {
+                info!("Received REKEY epoch={} from {}", epoch, source);
+                self.ensure_member(source);
+                self.auth.install_session(source, epoch, key);
             }
         }
 
         Ok(())
     }
 
-    /// Ensure a member exists in our list, add if not present
+    /// Record that we just heard from `source`: refresh its liveness and feed
+    /// the inter-arrival distribution that drives phi-accrual detection.
+    fn note_heard(&mut self, source: SocketAddr) {
+        if source == self.local_addr {
+            return;
+        }
+        self.ensure_member(source);
+        if let Some(member) = self.members.members.get_mut(&source) {
+            member.record_heard();
+        }
+    }
+
+    /// Whether this worker's shard owns `addr`. A node only holds and probes
+    /// members it owns, so a peer's membership state is never split across
+    /// workers. Indirect-probe traffic is the exception: the Ping to a
+    /// PingReq's target and the AckRelay to its requester are sent by
+    /// whichever worker handled the triggering message.
+    fn owns(&self, addr: SocketAddr) -> bool {
+        shard_for(addr, self.workers) == self.shard
+    }
+
+    /// Ensure a member exists in our list, add if not present. Members outside
+    /// this worker's shard are owned by another worker and are ignored here —
+    /// including any gossiped about by a peer we talk to.
     fn ensure_member(&mut self, addr: SocketAddr) {
-        if addr != self.local_addr && !self.members.members.contains_key(&addr) {
+        if addr == self.local_addr || !self.owns(addr) {
+            return;
+        }
+        if !self.members.members.contains_key(&addr) {
             info!("Adding new member: {}", addr);
-            self.members.members.insert(
-                addr,
-                Member {
+            self.members
+                .members
+                .insert(addr, Member::new(PeerState::Active));
+        }
+    }
+
+    /// Apply a gossiped membership update to our local view using SWIM's
+    /// `(incarnation, state)` precedence: a higher incarnation always wins, and
+    /// for an equal incarnation the higher-precedence state wins
+    /// (`Active < Suspect < Dead`). `last_state_change` only moves when the
+    /// stored tuple actually changes.
+    ///
+    /// An update asserting that *we* are Suspect or Dead is refuted: we advance
+    /// our own incarnation strictly past the incoming value and gossip a fresh
+    /// `Active` update about ourselves.
+    ///
+    /// A third-party update that supersedes our local view is re-queued for
+    /// dissemination, so knowledge of a state transition spreads epidemically
+    /// from every node that learns it rather than only the node that first
+    /// detected it.
+    fn merge_update(&mut self, addr: SocketAddr, incarnation: u32, state: PeerState) {
+        if addr == self.local_addr {
+            if matches!(state, PeerState::Suspect | PeerState::Dead) {
+                let refuted = self.members.refute_past(incarnation);
+                warn!(
+                    "Refuting {:?} claim about self; advancing incarnation to {}",
+                    state, refuted
+                );
+                self.disseminate(MembershipUpdate {
+                    addr: self.local_addr,
+                    incarnation: refuted,
                     state: PeerState::Active,
-                    incarnation: 0,
-                    last_state_change: Instant::now(),
-                },
-            );
+                });
+            }
+            return;
+        }
+
+        self.ensure_member(addr);
+        let changed = match self.members.members.get_mut(&addr) {
+            Some(member) => {
+                let supersedes = incarnation > member.incarnation
+                    || (incarnation == member.incarnation
+                        && state.precedence() > member.state.precedence());
+                if supersedes && (member.incarnation != incarnation || member.state != state) {
+                    member.incarnation = incarnation;
+                    member.state = state;
+                    member.last_state_change = Instant::now();
+                    true
+                } else {
+                    false
+                }
+            }
+            None => return,
+        };
+
+        if changed {
+            self.disseminate(MembershipUpdate {
+                addr,
+                incarnation,
+                state,
+            });
         }
     }
 
     /// Mark a member as active
     fn mark_active(&mut self, addr: SocketAddr) {
-        if let Some(member) = self.members.members.get_mut(&addr) {
-            if member.state != PeerState::Active {
+        let incarnation = match self.members.members.get_mut(&addr) {
+            Some(member) if member.state != PeerState::Active => {
                 info!("Member {} is now ACTIVE", addr);
                 member.state = PeerState::Active;
                 member.last_state_change = Instant::now();
+                member.incarnation
             }
-        } else {
-            self.ensure_member(addr);
-        }
+            Some(_) => return,
+            None => {
+                self.ensure_member(addr);
+                return;
+            }
+        };
+        self.disseminate(MembershipUpdate {
+            addr,
+            incarnation,
+            state: PeerState::Active,
+        });
     }
 
     /// Mark a member as suspect
     fn mark_suspect(&mut self, addr: SocketAddr) {
-        if let Some(member) = self.members.members.get_mut(&addr) {
-            if member.state == PeerState::Active {
+        let incarnation = match self.members.members.get_mut(&addr) {
+            Some(member) if member.state == PeerState::Active => {
                 warn!("Member {} is now SUSPECT", addr);
                 member.state = PeerState::Suspect;
                 member.last_state_change = Instant::now();
+                member.incarnation
             }
-        }
+            _ => return,
+        };
+        self.disseminate(MembershipUpdate {
+            addr,
+            incarnation,
+            state: PeerState::Suspect,
+        });
     }
 
     /// Mark a member as dead
     fn mark_dead(&mut self, addr: SocketAddr) {
-        if let Some(member) = self.members.members.get_mut(&addr) {
-            if member.state != PeerState::Dead {
+        let incarnation = match self.members.members.get_mut(&addr) {
+            Some(member) if member.state != PeerState::Dead => {
                 warn!("Member {} is now DEAD", addr);
                 member.state = PeerState::Dead;
                 member.last_state_change = Instant::now();
+                member.incarnation
             }
-        }
+            _ => return,
+        };
+        self.disseminate(MembershipUpdate {
+            addr,
+            incarnation,
+            state: PeerState::Dead,
+        });
     }
 
     /// Called every tick interval
@@ -331,15 +849,78 @@ impl Node {
         // 1. Check for timed-out probes
         self.check_probe_timeouts()?;
 
-        // 2. Check for suspects that should become dead
+        // 2. Suspect peers whose phi-accrual estimate exceeds the threshold
+        self.check_phi_suspicions();
+
+        // 3. Check for suspects that should become dead
         self.check_suspect_timeouts();
 
-        // 3. Probe a random active member
+        // 4. Probe a random active member
         self.probe_random_member()?;
 
+        // 5. Expire stale indirect probe requests
+        self.expire_indirect_requests();
+
+        // 6. Rotate per-peer session keys on schedule
+        self.rekey_counter += 1;
+        if self.rekey_counter % REKEY_INTERVAL == 0 {
+            self.rotate_session_keys()?;
+        }
+
+        Ok(())
+    }
+
+    /// Drop indirect probe requests whose target never acked in time.
+    fn expire_indirect_requests(&mut self) {
+        let now = Instant::now();
+        self.indirect_requests
+            .lock()
+            .unwrap()
+            .retain(|r| now.duration_since(r.created_at) <= INDIRECT_REQUEST_TIMEOUT);
+    }
+
+    /// Advertise a fresh session key to every non-dead peer, keeping long-lived
+    /// clusters rotating without dropping traffic.
+    fn rotate_session_keys(&mut self) -> Result<()> {
+        let peers: Vec<SocketAddr> = self
+            .members
+            .members
+            .iter()
+            .filter(|(_, m)| m.state != PeerState::Dead)
+            .map(|(&addr, _)| addr)
+            .collect();
+
+        for peer in peers {
+            let (epoch, key) = self.auth.rotate(peer);
+            let rekey = Message::Rekey {
+                from: self.local_addr,
+                epoch,
+                key,
+            };
+            self.queue_send(rekey, peer)?;
+        }
+
         Ok(())
     }
 
+    /// Suspect any active member whose phi-accrual suspicion level has crossed
+    /// the configured threshold.
+    fn check_phi_suspicions(&mut self) {
+        let now = Instant::now();
+        let suspects: Vec<SocketAddr> = self
+            .members
+            .members
+            .iter()
+            .filter(|(_, m)| m.state == PeerState::Active && m.phi(now) > self.phi_threshold)
+            .map(|(&addr, _)| addr)
+            .collect();
+
+        for addr in suspects {
+            warn!("Member {} exceeded phi threshold, marking suspect", addr);
+            self.mark_suspect(addr);
+        }
+    }
+
     fn count_by_state(&self, state: PeerState) -> usize {
         self.members
             .members
@@ -348,17 +929,29 @@ impl Node {
             .count()
     }
 
+    /// Adaptive probe timeout for a target, falling back to the flat
+    /// `PROBE_TIMEOUT` when the peer is unknown.
+    fn timeout_for(&self, target: SocketAddr) -> Duration {
+        self.members
+            .members
+            .get(&target)
+            .map(|m| m.effective_timeout())
+            .unwrap_or(PROBE_TIMEOUT)
+    }
+
     fn check_probe_timeouts(&mut self) -> Result<()> {
         let now = Instant::now();
         let mut timed_out = Vec::new();
         let mut need_indirect = Vec::new();
+        let mut expired = Vec::new();
 
         for probe in &self.probes {
-            if now.duration_since(probe.sent_at) > PROBE_TIMEOUT {
+            if now.duration_since(probe.sent_at) > self.timeout_for(probe.target) {
                 if !probe.indirect_sent {
                     need_indirect.push((probe.target, probe.seq));
                 } else {
                     timed_out.push(probe.target);
+                    expired.push((probe.seq, probe.target));
                 }
             }
         }
@@ -386,7 +979,7 @@ impl Node {
 
         // Remove timed out probes
         self.probes
-            .retain(|p| now.duration_since(p.sent_at) <= PROBE_TIMEOUT || !p.indirect_sent);
+            .retain(|p| !expired.iter().any(|&(seq, target)| seq == p.seq && target == p.target));
 
         Ok(())
     }
@@ -432,82 +1025,428 @@ impl Node {
         self.send_ping(target)?;
         Ok(())
     }
+}
 
-    pub fn event_loop(&mut self) -> Result<()> {
-        let mut poll = Poll::new()?;
-        let mut events = Events::with_capacity(128);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        poll.registry()
-            .register(&mut self.socket, UDP_SOCKET, Interest::READABLE | Interest::WRITABLE)?;
+    fn identity() -> Identity {
+        Identity::new([0u8; 32], HashMap::new())
+    }
 
-        info!("Event loop started");
-        let mut buf = [0; 1 << 16];
+    fn node(addr: &str) -> Node {
+        Node::new(
+            addr.parse().unwrap(),
+            b"cluster",
+            identity(),
+            0,
+            1,
+            Arc::new(AtomicU32::new(0)),
+            Arc::new(Mutex::new(Vec::new())),
+        )
+    }
 
-        loop {
-            // Calculate timeout until next tick
-            let elapsed = self.last_tick.elapsed();
-            let timeout = if elapsed >= TICK_INTERVAL {
-                Some(Duration::ZERO)
-            } else {
-                Some(TICK_INTERVAL - elapsed)
-            };
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
 
-            if let Err(e) = poll.poll(&mut events, timeout) {
-                if e.kind() == io::ErrorKind::Interrupted {
-                    continue;
-                }
-                return Err(e.into());
-            }
+    #[test]
+    fn merge_update_precedence() {
+        let mut n = node("127.0.0.1:8000");
+        let peer = addr("127.0.0.1:8001");
+        n.ensure_member(peer);
+
+        // A higher incarnation always wins.
+        n.merge_update(peer, 5, PeerState::Suspect);
+        assert_eq!(n.members.members[&peer].incarnation, 5);
+        assert_eq!(n.members.members[&peer].state, PeerState::Suspect);
+
+        // A lower incarnation is ignored.
+        n.merge_update(peer, 3, PeerState::Active);
+        assert_eq!(n.members.members[&peer].incarnation, 5);
+        assert_eq!(n.members.members[&peer].state, PeerState::Suspect);
+
+        // At equal incarnation the higher-precedence state wins.
+        n.merge_update(peer, 5, PeerState::Dead);
+        assert_eq!(n.members.members[&peer].state, PeerState::Dead);
+
+        // At equal incarnation a lower-precedence state does not.
+        n.merge_update(peer, 5, PeerState::Active);
+        assert_eq!(n.members.members[&peer].state, PeerState::Dead);
+    }
 
-            // Check if tick is due
-            if self.last_tick.elapsed() >= TICK_INTERVAL {
-                self.tick()?;
-                self.last_tick = Instant::now();
-            }
+    #[test]
+    fn merge_update_forwards_third_party_transitions() {
+        // A detects a transition and gossips it to B; B must re-gossip what it
+        // merely learned so it keeps spreading to C, which never hears from A.
+        let mut a = node("127.0.0.1:8500");
+        let mut b = node("127.0.0.1:8501");
+        let mut c = node("127.0.0.1:8502");
+
+        let flaky = addr("127.0.0.1:8503");
+        a.ensure_member(flaky);
+        a.mark_suspect(flaky);
+
+        let ping_to_b = Message::Ping {
+            seq: 1,
+            from: a.local_addr,
+            updates: a.select_updates(MAX_PIGGYBACK),
+        };
+        b.handle_message(a.local_addr, ping_to_b).unwrap();
+        assert_eq!(b.members.members[&flaky].state, PeerState::Suspect);
 
-            // Always try to flush send queue (edge-triggered epoll won't re-notify)
-            let _ = self.flush_send_queue();
-
-            // Process socket events
-            for event in events.iter() {
-                match event.token() {
-                    UDP_SOCKET => {
-                        // Handle readable
-                        if event.is_readable() {
-                            loop {
-                                match self.socket.recv_from(&mut buf) {
-                                    Ok((packet_size, _source)) => {
-                                        match Message::from_bytes(&buf[..packet_size]) {
-                                            Ok(msg) => {
-                                                if let Err(e) = self.handle_message(msg) {
-                                                    warn!("Error handling message: {}", e);
-                                                }
-                                            }
-                                            Err(e) => {
-                                                warn!("Failed to parse message: {}", e);
-                                            }
-                                        }
-                                    }
-                                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-                                        break;
-                                    }
-                                    Err(e) => {
-                                        return Err(e.into());
-                                    }
-                                }
-                            }
-                        }
-
-                        // Handle writable
-                        if event.is_writable() {
-                            self.flush_send_queue()?;
-                        }
-                    }
-                    _ => {
-                        warn!("Unexpected event token");
-                    }
-                }
-            }
+        let ping_to_c = Message::Ping {
+            seq: 1,
+            from: b.local_addr,
+            updates: b.select_updates(MAX_PIGGYBACK),
+        };
+        c.handle_message(b.local_addr, ping_to_c).unwrap();
+
+        assert_eq!(c.members.members[&flaky].state, PeerState::Suspect);
+        assert!(
+            c.dissemination
+                .iter()
+                .any(|u| u.update.addr == flaky && u.update.state == PeerState::Suspect)
+        );
+    }
+
+    #[test]
+    fn merge_update_refutes_self() {
+        let mut n = node("127.0.0.1:8100");
+        let me = n.local_addr;
+        n.merge_update(me, 7, PeerState::Suspect);
+
+        // We advance our own incarnation strictly past the claim and gossip
+        // a fresh Active update about ourselves.
+        assert!(n.members.self_seq.load(Ordering::Relaxed) > 7);
+        assert!(
+            n.dissemination
+                .iter()
+                .any(|u| u.update.addr == me && u.update.state == PeerState::Active)
+        );
+    }
+
+    #[test]
+    fn select_updates_respects_max_piggyback() {
+        let mut n = node("127.0.0.1:8700");
+        for i in 0..10u16 {
+            n.disseminate(MembershipUpdate {
+                addr: addr(&format!("127.0.0.1:88{i:02}")),
+                incarnation: 1,
+                state: PeerState::Active,
+            });
         }
+
+        let selected = n.select_updates(4);
+        assert_eq!(selected.len(), 4);
+    }
+
+    #[test]
+    fn select_updates_sends_lowest_retransmit_count_first() {
+        let mut n = node("127.0.0.1:8701");
+        let fresh = addr("127.0.0.1:8710");
+        let stale = addr("127.0.0.1:8711");
+
+        // `stale` has already gone out twice; `fresh` never has. With a
+        // budget of one, the least-retransmitted update should win.
+        n.dissemination.push(PendingUpdate {
+            update: MembershipUpdate {
+                addr: stale,
+                incarnation: 1,
+                state: PeerState::Active,
+            },
+            retransmits: 2,
+        });
+        n.dissemination.push(PendingUpdate {
+            update: MembershipUpdate {
+                addr: fresh,
+                incarnation: 1,
+                state: PeerState::Active,
+            },
+            retransmits: 0,
+        });
+
+        let selected = n.select_updates(1);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].addr, fresh);
+    }
+
+    #[test]
+    fn select_updates_evicts_once_retransmit_budget_is_spent() {
+        // With no known members, ceil(LAMBDA * log2(0+1)) is 0, clamped to
+        // the floor of 1: a single transmission exhausts the budget.
+        let mut n = node("127.0.0.1:8702");
+        let peer = addr("127.0.0.1:8703");
+        n.disseminate(MembershipUpdate {
+            addr: peer,
+            incarnation: 1,
+            state: PeerState::Suspect,
+        });
+        assert_eq!(n.retransmit_budget(), 1);
+
+        let selected = n.select_updates(MAX_PIGGYBACK);
+        assert_eq!(selected.len(), 1);
+        assert!(n.dissemination.is_empty());
+    }
+
+    #[test]
+    fn select_updates_keeps_update_until_its_budget_is_spent() {
+        // One known member raises the budget to ceil(3 * log2(2)) = 3, so the
+        // update should survive the first two rounds and only evict on the
+        // third.
+        let mut n = node("127.0.0.1:8704");
+        n.ensure_member(addr("127.0.0.1:8705"));
+        let peer = addr("127.0.0.1:8706");
+        n.disseminate(MembershipUpdate {
+            addr: peer,
+            incarnation: 1,
+            state: PeerState::Suspect,
+        });
+        assert_eq!(n.retransmit_budget(), 3);
+
+        assert_eq!(n.select_updates(MAX_PIGGYBACK).len(), 1);
+        assert_eq!(n.dissemination.len(), 1);
+        assert_eq!(n.select_updates(MAX_PIGGYBACK).len(), 1);
+        assert_eq!(n.dissemination.len(), 1);
+        assert_eq!(n.select_updates(MAX_PIGGYBACK).len(), 1);
+        assert!(n.dissemination.is_empty());
+    }
+
+    #[test]
+    fn rekey_does_not_spend_pending_update_retransmit_budget() {
+        // Rekey carries no gossip (`Message::set_updates` is a no-op for it),
+        // so sending one must not touch a pending update's retransmit count.
+        let mut n = node("127.0.0.1:8720");
+        n.ensure_member(addr("127.0.0.1:8721"));
+        let peer = addr("127.0.0.1:8722");
+        n.disseminate(MembershipUpdate {
+            addr: peer,
+            incarnation: 1,
+            state: PeerState::Suspect,
+        });
+        let budget_before = n.dissemination[0].retransmits;
+
+        n.rotate_session_keys().unwrap();
+
+        assert_eq!(n.dissemination.len(), 1);
+        assert_eq!(n.dissemination[0].retransmits, budget_before);
+    }
+
+    #[test]
+    fn ack_relay_confirms_pending_probe() {
+        let mut n = node("127.0.0.1:8200");
+        let intermediary = addr("127.0.0.1:8201");
+        let target = addr("127.0.0.1:8202");
+        n.probes.push(PendingProbe {
+            seq: 42,
+            target,
+            sent_at: Instant::now(),
+            indirect_sent: true,
+        });
+
+        let relay = Message::AckRelay {
+            seq: 42,
+            from: intermediary,
+            target,
+            updates: Vec::new(),
+        };
+        n.handle_message(intermediary, relay).unwrap();
+
+        assert!(!n.probes.iter().any(|p| p.seq == 42 && p.target == target));
+        assert_eq!(n.members.members[&target].state, PeerState::Active);
+    }
+
+    #[test]
+    fn ping_req_tracks_and_relays_ack() {
+        let mut n = node("127.0.0.1:8300");
+        let requester = addr("127.0.0.1:8301");
+        let target = addr("127.0.0.1:8302");
+
+        n.handle_message(
+            requester,
+            Message::PingReq {
+                seq: 7,
+                from: requester,
+                target,
+                updates: Vec::new(),
+            },
+        )
+        .unwrap();
+        assert!(
+            n.indirect_requests
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|r| r.seq == 7 && r.target == target && r.requester == requester)
+        );
+        // We probe the target on the requester's behalf.
+        assert!(n.drain_outbound().iter().any(|p| p.target == target));
+
+        // When the target acks, we relay it back to the original requester.
+        n.handle_message(
+            target,
+            Message::Ack {
+                seq: 7,
+                from: target,
+                updates: Vec::new(),
+            },
+        )
+        .unwrap();
+        assert!(n.drain_outbound().iter().any(|p| p.target == requester));
+    }
+
+    #[test]
+    fn indirect_relay_works_when_requester_and_target_are_on_different_shards() {
+        // The PingReq lands on whichever shard services the requester, while
+        // the target's Ack is routed by the target's own address and can land
+        // on a different shard entirely. Both must see the same pending
+        // request for the relay to fire.
+        let workers = 2;
+        let indirect_requests = Arc::new(Mutex::new(Vec::new()));
+        let mut requester_shard = Node::new(
+            addr("127.0.0.1:8900"),
+            b"cluster",
+            identity(),
+            0,
+            workers,
+            Arc::new(AtomicU32::new(0)),
+            Arc::clone(&indirect_requests),
+        );
+        let mut target_shard = Node::new(
+            addr("127.0.0.1:8901"),
+            b"cluster",
+            identity(),
+            1,
+            workers,
+            Arc::new(AtomicU32::new(0)),
+            indirect_requests,
+        );
+
+        let requester = addr("127.0.0.1:8902");
+        let target = addr("127.0.0.1:8903");
+
+        requester_shard
+            .handle_message(
+                requester,
+                Message::PingReq {
+                    seq: 11,
+                    from: requester,
+                    target,
+                    updates: Vec::new(),
+                },
+            )
+            .unwrap();
+
+        target_shard
+            .handle_message(
+                target,
+                Message::Ack {
+                    seq: 11,
+                    from: target,
+                    updates: Vec::new(),
+                },
+            )
+            .unwrap();
+
+        assert!(
+            target_shard
+                .drain_outbound()
+                .iter()
+                .any(|p| p.target == requester)
+        );
+    }
+
+    #[test]
+    fn cross_shard_update_is_forwarded_not_dropped() {
+        let workers = 2;
+        let self_seq = Arc::new(AtomicU32::new(0));
+        let mut n0 = Node::new(
+            addr("127.0.0.1:8600"),
+            b"cluster",
+            identity(),
+            0,
+            workers,
+            Arc::clone(&self_seq),
+            Arc::new(Mutex::new(Vec::new())),
+        );
+        let mut n1 = Node::new(
+            addr("127.0.0.1:8601"),
+            b"cluster",
+            identity(),
+            1,
+            workers,
+            self_seq,
+            Arc::new(Mutex::new(Vec::new())),
+        );
+
+        // Find a subject address owned by shard 1 (i.e. not by n0's shard 0).
+        let mut port = 9000u16;
+        let mut subject = addr(&format!("127.0.0.1:{port}"));
+        while shard_for(subject, workers) != 1 {
+            port += 1;
+            subject = addr(&format!("127.0.0.1:{port}"));
+        }
+
+        let sender = addr("127.0.0.1:8700");
+        let ping = Message::Ping {
+            seq: 1,
+            from: sender,
+            updates: vec![MembershipUpdate {
+                addr: subject,
+                incarnation: 1,
+                state: PeerState::Suspect,
+            }],
+        };
+
+        // n0 (shard 0) receives the packet carrying the update, but the
+        // subject belongs to shard 1: it must not merge it locally...
+        n0.handle_message(sender, ping).unwrap();
+        assert!(!n0.members.members.contains_key(&subject));
+
+        // ...and must instead queue it for the pipeline to forward.
+        let forwarded = n0.drain_forwards();
+        assert_eq!(forwarded.len(), 1);
+        assert_eq!(forwarded[0].addr, subject);
+
+        // Once forwarded, the owning shard applies it.
+        n1.merge_forwarded(forwarded.into_iter().next().unwrap());
+        assert_eq!(n1.members.members[&subject].state, PeerState::Suspect);
+    }
+
+    #[test]
+    fn spoofed_from_is_rejected() {
+        let mut n = node("127.0.0.1:8400");
+        let source = addr("127.0.0.1:8401");
+        let spoofed = addr("127.0.0.1:8402");
+
+        // A Ping whose `from` disagrees with the source is dropped: no member
+        // is learned and no Ack is queued.
+        n.handle_message(
+            source,
+            Message::Ping {
+                seq: 1,
+                from: spoofed,
+                updates: Vec::new(),
+            },
+        )
+        .unwrap();
+        assert!(!n.members.members.contains_key(&source));
+        assert!(n.drain_outbound().is_empty());
+    }
+
+    #[test]
+    fn phi_rises_with_elapsed_silence() {
+        let mut m = Member::new(PeerState::Active);
+        for _ in 0..PHI_MIN_SAMPLES {
+            m.intervals.push_back(Duration::from_secs_f64(1.0));
+        }
+        let base = Instant::now();
+        m.last_heard = base;
+
+        let soon = base + Duration::from_secs_f64(1.0);
+        let late = base + Duration::from_secs_f64(10.0);
+        assert!(m.phi(soon) < m.phi(late));
+        assert!(m.phi(late) > PHI_THRESHOLD);
     }
 }
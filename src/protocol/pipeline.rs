@@ -0,0 +1,219 @@
+use std::{
+    net::{SocketAddr, UdpSocket},
+    sync::{
+        Arc, Mutex,
+        atomic::AtomicU32,
+        mpsc::{Receiver, RecvTimeoutError, SyncSender, sync_channel},
+    },
+    thread,
+};
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use crate::protocol::crypto::Identity;
+use crate::protocol::messages::MembershipUpdate;
+use crate::protocol::node::{IndirectRequest, Node, OutboundPacket, TICK_INTERVAL, shard_for};
+
+/// Bounded capacity of each protocol worker's inbound queue. When a worker
+/// falls behind, the socket worker blocks on send, applying backpressure
+/// instead of letting the queue grow without bound.
+const INBOUND_CAPACITY: usize = 1024;
+/// Bounded capacity of the shared outbound queue drained by the writer.
+const OUTBOUND_CAPACITY: usize = 1024;
+/// Largest datagram we will read from the socket.
+const MAX_DATAGRAM: usize = 1 << 16;
+
+/// A raw datagram handed from a socket worker to a protocol worker.
+struct RawPacket {
+    data: Vec<u8>,
+    source: SocketAddr,
+}
+
+/// Construction state shared by every protocol worker, bundled so
+/// `protocol_worker`'s signature stays within clippy's argument-count lint.
+struct WorkerConfig {
+    cluster_secret: Vec<u8>,
+    identity: Identity,
+    shard: usize,
+    workers: usize,
+    self_seq: Arc<AtomicU32>,
+    indirect_requests: Arc<Mutex<Vec<IndirectRequest>>>,
+    peers: Vec<SyncSender<WorkerInput>>,
+}
+
+/// Everything a protocol worker's inbound channel can carry: a datagram off
+/// the wire, or a piggybacked update forwarded by a sibling worker because
+/// this shard owns the update's subject (see `Node::drain_forwards`).
+enum WorkerInput {
+    Packet(RawPacket),
+    Forwarded(MembershipUpdate),
+}
+
+/// Multi-worker packet pipeline: socket workers own the UDP socket and do only
+/// `recv_from`/`send_to`, while a pool of protocol workers parse datagrams and
+/// run the SWIM state machine over a shard of the membership. Bounded channels
+/// connect the two so a slow handler applies backpressure rather than growing
+/// an unbounded send queue.
+pub struct Pipeline;
+
+impl Pipeline {
+    /// Bind `bind_addr` and run the pipeline until a socket error occurs.
+    pub fn run(
+        bind_addr: SocketAddr,
+        cluster_secret: Vec<u8>,
+        identity: Identity,
+        seeds: Vec<SocketAddr>,
+        workers: usize,
+    ) -> Result<()> {
+        let workers = workers.max(1);
+        let socket = Arc::new(UdpSocket::bind(bind_addr).context("failed to bind socket")?);
+        let local_addr = socket.local_addr()?;
+        info!(
+            "Pipeline listening on {} with {} protocol workers",
+            local_addr, workers
+        );
+
+        let (outbound_tx, outbound_rx) = sync_channel::<OutboundPacket>(OUTBOUND_CAPACITY);
+
+        // Shared so every worker advertises and refutes under one monotonic
+        // self incarnation, regardless of which shard a peer is routed to.
+        let self_seq = Arc::new(AtomicU32::new(0));
+
+        // Shared because an indirect probe's requester and target generally
+        // hash to different shards; the target's Ack is handled wherever it
+        // lands and must still find the entry the requester's shard created.
+        let indirect_requests = Arc::new(Mutex::new(Vec::<IndirectRequest>::new()));
+
+        // Every worker's inbound channel, built up front so each worker can
+        // also hold every *other* worker's sender — a piggybacked update whose
+        // subject lives in a different shard is forwarded peer-to-peer rather
+        // than merged locally (see `Node::drain_forwards`).
+        let mut inbound_txs = Vec::with_capacity(workers);
+        let mut inbound_rxs = Vec::with_capacity(workers);
+        for _ in 0..workers {
+            let (tx, rx) = sync_channel::<WorkerInput>(INBOUND_CAPACITY);
+            inbound_txs.push(tx);
+            inbound_rxs.push(Some(rx));
+        }
+
+        for (shard, rx_slot) in inbound_rxs.iter_mut().enumerate() {
+            let rx = rx_slot.take().expect("each shard's receiver is taken once");
+
+            let seeds_for_shard: Vec<SocketAddr> = seeds
+                .iter()
+                .copied()
+                .filter(|addr| shard_for(*addr, workers) == shard)
+                .collect();
+            let outbound = outbound_tx.clone();
+            let config = WorkerConfig {
+                cluster_secret: cluster_secret.clone(),
+                identity: identity.clone(),
+                shard,
+                workers,
+                self_seq: Arc::clone(&self_seq),
+                indirect_requests: Arc::clone(&indirect_requests),
+                peers: inbound_txs.clone(),
+            };
+
+            thread::Builder::new()
+                .name(format!("swim-protocol-{shard}"))
+                .spawn(move || protocol_worker(local_addr, config, seeds_for_shard, rx, outbound))
+                .context("failed to spawn protocol worker")?;
+        }
+        drop(outbound_tx);
+
+        // Writer: drain the outbound queue to the wire.
+        let writer_socket = Arc::clone(&socket);
+        thread::Builder::new()
+            .name("swim-socket-writer".to_string())
+            .spawn(move || {
+                for packet in outbound_rx {
+                    if let Err(e) = writer_socket.send_to(&packet.data, packet.target) {
+                        warn!("send_to {} failed: {}", packet.target, e);
+                    }
+                }
+            })
+            .context("failed to spawn socket writer")?;
+
+        // Reader: runs on this thread, dispatching datagrams to shards. A full
+        // inbound channel blocks the send, which stops us reading — backpressure.
+        let mut buf = [0u8; MAX_DATAGRAM];
+        loop {
+            let (size, source) = socket.recv_from(&mut buf)?;
+            let shard = shard_for(source, workers);
+            let packet = RawPacket {
+                data: buf[..size].to_vec(),
+                source,
+            };
+            if inbound_txs[shard].send(WorkerInput::Packet(packet)).is_err() {
+                warn!("protocol worker {} has exited", shard);
+            }
+        }
+    }
+}
+
+/// A single protocol worker: owns a membership shard, services inbound
+/// datagrams, and ticks the SWIM state machine on schedule.
+fn protocol_worker(
+    local_addr: SocketAddr,
+    config: WorkerConfig,
+    seeds: Vec<SocketAddr>,
+    inbound: Receiver<WorkerInput>,
+    outbound: SyncSender<OutboundPacket>,
+) {
+    let mut node = Node::new(
+        local_addr,
+        &config.cluster_secret,
+        config.identity,
+        config.shard,
+        config.workers,
+        config.self_seq,
+        config.indirect_requests,
+    );
+
+    for seed in seeds {
+        if let Err(e) = node.join(seed.to_string()) {
+            warn!("failed to join seed {}: {}", seed, e);
+        }
+    }
+    drain(&mut node, &outbound);
+
+    loop {
+        match inbound.recv_timeout(TICK_INTERVAL) {
+            Ok(WorkerInput::Packet(packet)) => node.process_datagram(packet.source, &packet.data),
+            Ok(WorkerInput::Forwarded(update)) => node.merge_forwarded(update),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Err(e) = node.maybe_tick() {
+            warn!("tick error: {}", e);
+        }
+        drain(&mut node, &outbound);
+        forward(&mut node, config.workers, &config.peers);
+    }
+}
+
+/// Forward every queued datagram to the outbound channel, blocking (and thus
+/// backpressuring this worker) when the writer is behind.
+fn drain(node: &mut Node, outbound: &SyncSender<OutboundPacket>) {
+    for packet in node.drain_outbound() {
+        if outbound.send(packet).is_err() {
+            return;
+        }
+    }
+}
+
+/// Route every update this worker learned about but doesn't own to the peer
+/// worker that does, so piggybacked gossip about a peer is merged by the
+/// shard actually tracking it rather than dropped by whichever shard the
+/// carrying packet happened to land on.
+fn forward(node: &mut Node, workers: usize, peers: &[SyncSender<WorkerInput>]) {
+    for update in node.drain_forwards() {
+        let owner = shard_for(update.addr, workers);
+        if peers[owner].send(WorkerInput::Forwarded(update)).is_err() {
+            warn!("protocol worker {} has exited", owner);
+        }
+    }
+}